@@ -0,0 +1,119 @@
+use clap_derive::ValueEnum;
+
+/// size (in bytes) huge-page allocations are rounded up to; the platform's actual huge page size
+/// is not queried
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// allocation backend for benchmark buffers
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Alloc {
+    /// a plain `Vec<u8>`
+    Vec,
+    /// an anonymous `mmap` region, backed by the default (usually 4 KiB) page size
+    Mmap,
+    /// an anonymous `mmap` region mapped with `MAP_HUGETLB`
+    Hugepage,
+}
+
+/// an anonymous `mmap` region, optionally backed by huge pages, exposed as a plain byte slice so
+/// callers don't need to know how the memory was obtained
+pub(crate) struct MappedRegion {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl MappedRegion {
+    pub(crate) fn new(size: usize, hugepage: bool) -> Self {
+        let len = if hugepage {
+            size.div_ceil(HUGE_PAGE_SIZE) * HUGE_PAGE_SIZE
+        } else {
+            size
+        };
+
+        let mut flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS;
+        if hugepage {
+            flags |= libc::MAP_HUGETLB;
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                -1,
+                0,
+            )
+        };
+        assert_ne!(
+            ptr,
+            libc::MAP_FAILED,
+            "mmap failed: {}",
+            std::io::Error::last_os_error()
+        );
+
+        Self { ptr, len }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr as *mut u8, self.len) }
+    }
+}
+
+impl Drop for MappedRegion {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+// SAFETY: the region owns its memory exclusively and carries no thread-affinity, so it's sound
+// to hand it to another thread.
+unsafe impl Send for MappedRegion {}
+
+/// a benchmark buffer backed by either a `Vec<u8>` or an `mmap`/huge-page region
+///
+/// `memcpy_test`/`memset_test` stay slice-based regardless of which backend was picked: call
+/// `as_slice`/`as_mut_slice` and don't worry about how the memory was obtained or released.
+pub enum Buffer {
+    Vec(Vec<u8>),
+    Mapped(MappedRegion),
+}
+
+impl Buffer {
+    /// allocates `size` bytes with the given backend and fills them with `fill`, so the page
+    /// faults happen here instead of during the timed region
+    pub fn new(size: usize, alloc: Alloc, fill: u8) -> Self {
+        match alloc {
+            Alloc::Vec => {
+                let mut v = Vec::with_capacity(size);
+                v.resize(size, fill);
+                Buffer::Vec(v)
+            }
+            Alloc::Mmap | Alloc::Hugepage => {
+                let mut region = MappedRegion::new(size, matches!(alloc, Alloc::Hugepage));
+                region.as_mut_slice().fill(fill);
+                Buffer::Mapped(region)
+            }
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            Buffer::Vec(v) => v.as_slice(),
+            Buffer::Mapped(m) => m.as_slice(),
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            Buffer::Vec(v) => v.as_mut_slice(),
+            Buffer::Mapped(m) => m.as_mut_slice(),
+        }
+    }
+}
@@ -1,9 +1,37 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(transparent)]
 pub struct Bytes(pub f64);
 
+impl FromStr for Bytes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split_at = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (num, unit) = s.split_at(split_at);
+
+        let num: f64 = num
+            .parse()
+            .map_err(|_| format!("{s:?} is not a valid byte count"))?;
+
+        let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1.0,
+            "KB" => 1e3,
+            "MB" => 1e6,
+            "GB" => 1e9,
+            "TB" => 1e12,
+            unit => return Err(format!("unknown byte unit {unit:?}")),
+        };
+
+        Ok(Bytes(num * multiplier))
+    }
+}
+
 impl Display for Bytes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let bytes = self.0;
@@ -33,4 +61,12 @@ mod tests {
 
         assert_eq!(result, "13.98 GB");
     }
+
+    #[test]
+    fn test_num_parsing() {
+        assert_eq!("100".parse::<Bytes>().unwrap().0, 100.0);
+        assert_eq!("1.5 MB".parse::<Bytes>().unwrap().0, 1.5e6);
+        assert_eq!("2GB".parse::<Bytes>().unwrap().0, 2e9);
+        assert!("2xb".parse::<Bytes>().is_err());
+    }
 }
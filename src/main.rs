@@ -1,9 +1,17 @@
+mod buffer;
 mod fmtbytes;
+mod stats;
+mod throttle;
+
+use std::sync::Mutex;
 
 use clap::Parser;
 use clap_derive::{Parser, Subcommand};
 
+use crate::buffer::{Alloc, Buffer};
 use crate::fmtbytes::Bytes;
+use crate::stats::OutputFormat;
+use crate::throttle::{TokenBucket, THROTTLE_CHUNK_SIZE};
 
 #[derive(Debug, Subcommand)]
 enum Cmd {
@@ -15,6 +23,20 @@ enum Cmd {
         /// chunks
         #[arg(short, long, default_value_t = std::thread::available_parallelism().map(|x|x.get()).unwrap_or(1))]
         threads: usize,
+        /// backend used to allocate the source/destination buffers
+        #[arg(long, value_enum, default_value_t = Alloc::Vec)]
+        alloc: Alloc,
+        /// stream the copy through a double-buffered producer/consumer pipeline instead of
+        /// splitting the buffer across independent threads: one thread reads `src` in
+        /// `max(threads, 2)` segments while another drains the previously read segment into `dst`, so
+        /// reading and writing overlap instead of running as one synchronous
+        /// `copy_from_slice`. `--max-throughput` is not honored in this mode.
+        #[arg(long)]
+        pipeline: bool,
+        /// cap the achieved throughput of the copy loop at this many bytes/sec (e.g. "100MB",
+        /// "2GB"), throttled via a token-bucket limiter
+        #[arg(long)]
+        max_throughput: Option<Bytes>,
     },
     Memset {
         /// buffer size in bytes
@@ -24,7 +46,89 @@ enum Cmd {
         /// chunks
         #[arg(short, long, default_value_t = std::thread::available_parallelism().map(|x|x.get()).unwrap_or(1))]
         threads: usize,
+        /// backend used to allocate the buffer
+        #[arg(long, value_enum, default_value_t = Alloc::Vec)]
+        alloc: Alloc,
+        /// cap the achieved throughput of the set loop at this many bytes/sec (e.g. "100MB",
+        /// "2GB"), throttled via a token-bucket limiter
+        #[arg(long)]
+        max_throughput: Option<Bytes>,
+    },
+    /// pure read bandwidth: stream the whole buffer through a reduction instead of writing
+    /// anything, complementing the write-heavy `Memset` and read+write `Memcpy`
+    Memread {
+        /// buffer size in bytes
+        #[arg(short, long, default_value_t = 1048576)]
+        size: usize,
+        /// number of threads to perform the read on, splitting the buffer into threads number of
+        /// chunks
+        #[arg(short, long, default_value_t = std::thread::available_parallelism().map(|x|x.get()).unwrap_or(1))]
+        threads: usize,
     },
+    /// random-access read bandwidth: visit pages of the buffer in a shuffled order instead of
+    /// sequentially, to expose TLB/prefetcher effects
+    Randread {
+        /// buffer size in bytes
+        #[arg(short, long, default_value_t = 1048576)]
+        size: usize,
+        /// number of threads to perform the read on, splitting the shuffled page order into
+        /// threads number of chunks
+        #[arg(short, long, default_value_t = std::thread::available_parallelism().map(|x|x.get()).unwrap_or(1))]
+        threads: usize,
+        /// page size in bytes used to chunk the buffer for the random-access pattern
+        #[arg(short, long, default_value_t = 4096, value_parser = parse_nonzero_page_size)]
+        page_size: usize,
+    },
+    /// random-access write bandwidth: visit pages of the buffer in a shuffled order instead of
+    /// sequentially, to expose TLB/prefetcher effects
+    Randwrite {
+        /// buffer size in bytes
+        #[arg(short, long, default_value_t = 1048576)]
+        size: usize,
+        /// number of threads to perform the write on, splitting the shuffled page order into
+        /// threads number of chunks
+        #[arg(short, long, default_value_t = std::thread::available_parallelism().map(|x|x.get()).unwrap_or(1))]
+        threads: usize,
+        /// page size in bytes used to chunk the buffer for the random-access pattern
+        #[arg(short, long, default_value_t = 4096, value_parser = parse_nonzero_page_size)]
+        page_size: usize,
+    },
+}
+
+/// deterministic, dependency-free PRNG (splitmix64) used to seed the random-access page order
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// parses `--page-size`, rejecting 0 since it's divided into to compute the page count
+fn parse_nonzero_page_size(s: &str) -> Result<usize, String> {
+    let page_size: usize = s
+        .parse()
+        .map_err(|_| format!("{s:?} is not a valid page size"))?;
+    if page_size == 0 {
+        return Err("page size must be greater than 0".to_string());
+    }
+    Ok(page_size)
+}
+
+/// builds a `0..npages` index permutation with a seeded Fisher-Yates shuffle, so repeated runs
+/// visit pages in the same randomized order
+fn shuffled_page_order(npages: usize, seed: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..npages).collect();
+    let mut rng = SplitMix64(seed);
+    for i in (1..npages).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+    order
 }
 
 #[derive(Debug, Parser)]
@@ -37,47 +141,72 @@ struct Cli {
     /// number of iterations which are not reported
     #[arg(short, long, default_value_t = 5)]
     warmups: usize,
-}
 
-fn print_throughput_ghz(bytes_per_sec: f64) {
-    println!("{}/s", Bytes(bytes_per_sec));
+    /// how to print each test's summary statistics across its reported repetitions
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
 }
 
-fn memcpy_test(size: usize, threads: usize, repetitions: usize, warmups: usize) {
-    let mut src = Vec::<u8>::with_capacity(size);
-    let mut dst = Vec::<u8>::with_capacity(size);
-
+fn memcpy_test(
+    size: usize,
+    threads: usize,
+    alloc: Alloc,
+    max_throughput: Option<f64>,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
     // it's important to touch all allocated pages, we don't want to count the page faults the
     // first time they're used
-    // also, if we have initialized vectors, then we can use the nice slice APIs
-    src.resize(size, 0xBE);
-    dst.resize(size, 0xEF);
-
-    println!(
-        "memcpy test of {} on {threads} thread(s)",
-        Bytes(size as f64)
-    );
+    // also, if we keep the buffers slice-based, we can use the nice slice APIs regardless of the
+    // allocation backend
+    let src = Buffer::new(size, alloc, 0xBE);
+    let mut dst = Buffer::new(size, alloc, 0xEF);
+    let bucket = max_throughput.map(|rate| Mutex::new(TokenBucket::new(rate)));
+    let mut samples = Vec::with_capacity(repetitions - warmups);
+
+    if let OutputFormat::Human = output {
+        println!(
+            "memcpy test of {} on {threads} thread(s), alloc={alloc:?}",
+            Bytes(size as f64)
+        );
+    }
     for i in 0..repetitions {
         let mut start = std::time::Instant::now();
         let end;
-        if threads <= 1 {
-            dst.copy_from_slice(src.as_slice());
+        let src = &src.as_slice()[..size];
+        let dst = &mut dst.as_mut_slice()[..size];
+        if threads <= 1 && bucket.is_none() {
+            dst.copy_from_slice(src);
             end = std::time::Instant::now();
         } else {
-            let num_threads = threads;
+            let num_threads = threads.max(1);
 
             let latch = latches::sync::Latch::new(num_threads + 1);
+            let latch = &latch;
+            let bucket = bucket.as_ref();
 
             end = std::thread::scope(|s| {
                 let chunk_size = size.div_ceil(num_threads);
                 debug_assert!(chunk_size * num_threads >= size);
                 let mut threads = Vec::with_capacity(num_threads);
                 for (src, dst) in src.chunks(chunk_size).zip(dst.chunks_mut(chunk_size)) {
-                    threads.push(s.spawn(|| {
+                    threads.push(s.spawn(move || {
                         latch.count_down();
                         latch.wait();
                         let start = std::time::Instant::now();
-                        dst.copy_from_slice(src);
+                        match bucket {
+                            Some(bucket) => {
+                                for (src, dst) in src
+                                    .chunks(THROTTLE_CHUNK_SIZE)
+                                    .zip(dst.chunks_mut(THROTTLE_CHUNK_SIZE))
+                                {
+                                    bucket.lock().unwrap().consume(src.len() as f64);
+                                    dst.copy_from_slice(src);
+                                }
+                            }
+                            None => dst.copy_from_slice(src),
+                        }
                         (start, std::time::Instant::now())
                     }));
                 }
@@ -97,45 +226,136 @@ fn memcpy_test(size: usize, threads: usize, repetitions: usize, warmups: usize)
 
         if i >= warmups {
             let dur = end - start;
-            print!("throughput: ");
-            print_throughput_ghz(size as f64 / dur.as_secs_f64());
+            samples.push(size as f64 / dur.as_secs_f64());
         }
     }
+    stats::report("memcpy", size, threads, &samples, output);
 }
 
-fn memset_test(size: usize, threads: usize, repetitions: usize, warmups: usize) {
-    let mut buf = Vec::<u8>::with_capacity(size);
+fn pipeline_copy_test(
+    size: usize,
+    threads: usize,
+    alloc: Alloc,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
+    // it's important to touch all allocated pages, we don't want to count the page faults the
+    // first time they're used
+    let src = Buffer::new(size, alloc, 0xBE);
+    let mut dst = Buffer::new(size, alloc, 0xEF);
+
+    // the number of segments the buffer is split into; `threads` doubles as the pipeline depth
+    // here, since there's always exactly one producer and one consumer thread
+    let num_segments = threads.max(2);
+    let seg_size = size.div_ceil(num_segments);
+    let mut samples = Vec::with_capacity(repetitions - warmups);
+
+    if let OutputFormat::Human = output {
+        println!(
+            "pipelined memcpy test of {} on {num_segments} segment(s) of {}, alloc={alloc:?}",
+            Bytes(size as f64),
+            Bytes(seg_size as f64)
+        );
+    }
+    for i in 0..repetitions {
+        let src = &src.as_slice()[..size];
+        let dst = &mut dst.as_mut_slice()[..size];
+
+        // two scratch buffers handed back and forth between producer and consumer: the producer
+        // fills one while the consumer drains the other, so reading and writing overlap
+        let (segment_tx, segment_rx) = std::sync::mpsc::sync_channel::<(usize, Vec<u8>)>(1);
+        let (free_tx, free_rx) = std::sync::mpsc::sync_channel::<Vec<u8>>(2);
+        free_tx.send(vec![0u8; seg_size]).unwrap();
+        free_tx.send(vec![0u8; seg_size]).unwrap();
+        let consumer_free_tx = free_tx.clone();
+
+        let start = std::time::Instant::now();
+        std::thread::scope(|s| {
+            let producer = s.spawn(move || {
+                for (idx, chunk) in src.chunks(seg_size).enumerate() {
+                    let mut buf = free_rx.recv().unwrap();
+                    buf.clear();
+                    buf.extend_from_slice(chunk);
+                    segment_tx.send((idx, buf)).unwrap();
+                }
+            });
+            let consumer = s.spawn(move || {
+                let mut segments: Vec<_> = dst.chunks_mut(seg_size).collect();
+                for _ in 0..segments.len() {
+                    let (idx, buf) = segment_rx.recv().unwrap();
+                    segments[idx].copy_from_slice(&buf);
+                    let _ = consumer_free_tx.send(buf);
+                }
+            });
+            producer.join().unwrap();
+            consumer.join().unwrap();
+        });
+        let end = std::time::Instant::now();
+
+        if i >= warmups {
+            let dur = end - start;
+            samples.push(size as f64 / dur.as_secs_f64());
+        }
+    }
+    stats::report("pipelined memcpy", size, threads, &samples, output);
+}
 
+fn memset_test(
+    size: usize,
+    threads: usize,
+    alloc: Alloc,
+    max_throughput: Option<f64>,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
     // it's important to touch all allocated pages, we don't want to count the page faults the
     // first time they're used
-    // also, if we have initialized vectors, then we can use the nice slice APIs
-    buf.resize(size, 0xBE);
+    // also, if we keep the buffer slice-based, we can use the nice slice APIs regardless of the
+    // allocation backend
+    let mut buf = Buffer::new(size, alloc, 0xBE);
+    let bucket = max_throughput.map(|rate| Mutex::new(TokenBucket::new(rate)));
+    let mut samples = Vec::with_capacity(repetitions - warmups);
 
-    println!(
-        "memset test of {} on {threads} thread(s)",
-        Bytes(size as f64)
-    );
+    if let OutputFormat::Human = output {
+        println!(
+            "memset test of {} on {threads} thread(s), alloc={alloc:?}",
+            Bytes(size as f64)
+        );
+    }
     for i in 0..repetitions {
         let mut start = std::time::Instant::now();
         let end;
-        if threads <= 1 {
-            buf.as_mut_slice().fill(0xFE);
+        let buf = &mut buf.as_mut_slice()[..size];
+        if threads <= 1 && bucket.is_none() {
+            buf.fill(0xFE);
             end = std::time::Instant::now();
         } else {
-            let num_threads = threads;
+            let num_threads = threads.max(1);
 
             let latch = latches::sync::Latch::new(num_threads + 1);
+            let latch = &latch;
+            let bucket = bucket.as_ref();
 
             std::thread::scope(|s| {
                 let chunk_size = size.div_ceil(num_threads);
                 debug_assert!(chunk_size * num_threads >= size);
                 let mut threads = Vec::with_capacity(num_threads);
                 for b in buf.chunks_mut(chunk_size) {
-                    threads.push(s.spawn(|| {
+                    threads.push(s.spawn(move || {
                         latch.count_down();
                         latch.wait();
                         let start = std::time::Instant::now();
-                        b.fill(0xFE);
+                        match bucket {
+                            Some(bucket) => {
+                                for b in b.chunks_mut(THROTTLE_CHUNK_SIZE) {
+                                    bucket.lock().unwrap().consume(b.len() as f64);
+                                    b.fill(0xFE);
+                                }
+                            }
+                            None => b.fill(0xFE),
+                        }
                         (start, std::time::Instant::now())
                     }));
                 }
@@ -155,21 +375,351 @@ fn memset_test(size: usize, threads: usize, repetitions: usize, warmups: usize)
 
         if i >= warmups {
             let dur = end - start;
-            print!("throughput: ",);
-            print_throughput_ghz(size as f64 / dur.as_secs_f64());
+            samples.push(size as f64 / dur.as_secs_f64());
         }
     }
+    stats::report("memset", size, threads, &samples, output);
+}
+
+fn memread_test(
+    size: usize,
+    threads: usize,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
+    let mut buf = Vec::<u8>::with_capacity(size);
+
+    // it's important to touch all allocated pages, we don't want to count the page faults the
+    // first time they're used
+    buf.resize(size, 0xBE);
+    let mut samples = Vec::with_capacity(repetitions - warmups);
+
+    if let OutputFormat::Human = output {
+        println!(
+            "memread test of {} on {threads} thread(s)",
+            Bytes(size as f64)
+        );
+    }
+    for i in 0..repetitions {
+        let mut start = std::time::Instant::now();
+        let end;
+        let num_threads = threads.max(1);
+
+        let latch = latches::sync::Latch::new(num_threads + 1);
+        let latch = &latch;
+
+        end = std::thread::scope(|s| {
+            let chunk_size = size.div_ceil(num_threads);
+            debug_assert!(chunk_size * num_threads >= size);
+            let mut handles = Vec::with_capacity(num_threads);
+            for chunk in buf.chunks(chunk_size) {
+                handles.push(s.spawn(move || {
+                    latch.count_down();
+                    latch.wait();
+                    let start = std::time::Instant::now();
+                    let mut acc = 0u8;
+                    for &b in chunk {
+                        acc ^= b;
+                    }
+                    (start, std::time::Instant::now(), acc)
+                }));
+            }
+            latch.count_down();
+            latch.wait();
+            start = std::time::Instant::now();
+
+            let mut end = start;
+            let mut acc = 0u8;
+            for t in handles {
+                let (tstart, tend, tacc) = t.join().unwrap();
+                start = start.min(tstart);
+                end = end.max(tend);
+                acc ^= tacc;
+            }
+            std::hint::black_box(acc);
+            end
+        });
+
+        if i >= warmups {
+            let dur = end - start;
+            samples.push(size as f64 / dur.as_secs_f64());
+        }
+    }
+    stats::report("memread", size, threads, &samples, output);
+}
+
+fn randread_test(
+    size: usize,
+    threads: usize,
+    page_size: usize,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
+    let mut buf = Vec::<u8>::with_capacity(size);
+
+    // it's important to touch all allocated pages, we don't want to count the page faults the
+    // first time they're used
+    buf.resize(size, 0xBE);
+
+    let npages = size.div_ceil(page_size);
+    let order = shuffled_page_order(npages, 0xC0FFEE);
+    let mut samples = Vec::with_capacity(repetitions - warmups);
+
+    if let OutputFormat::Human = output {
+        println!(
+            "randread test of {} on {threads} thread(s), {} page(s) of {}",
+            Bytes(size as f64),
+            npages,
+            Bytes(page_size as f64)
+        );
+    }
+    for i in 0..repetitions {
+        let mut start = std::time::Instant::now();
+        let end;
+        let num_threads = threads.max(1);
+
+        let latch = latches::sync::Latch::new(num_threads + 1);
+        let latch = &latch;
+
+        end = std::thread::scope(|s| {
+            let chunk_size = order.len().div_ceil(num_threads);
+            debug_assert!(chunk_size * num_threads >= order.len());
+            let mut handles = Vec::with_capacity(num_threads);
+            for pages in order.chunks(chunk_size) {
+                let buf = buf.as_slice();
+                handles.push(s.spawn(move || {
+                    latch.count_down();
+                    latch.wait();
+                    let start = std::time::Instant::now();
+                    let mut acc = 0u8;
+                    for &page in pages {
+                        let off = page * page_size;
+                        let end = (off + page_size).min(buf.len());
+                        for &b in &buf[off..end] {
+                            acc ^= b;
+                        }
+                    }
+                    (start, std::time::Instant::now(), acc)
+                }));
+            }
+            latch.count_down();
+            latch.wait();
+            start = std::time::Instant::now();
+
+            let mut end = start;
+            let mut acc = 0u8;
+            for t in handles {
+                let (tstart, tend, tacc) = t.join().unwrap();
+                start = start.min(tstart);
+                end = end.max(tend);
+                acc ^= tacc;
+            }
+            std::hint::black_box(acc);
+            end
+        });
+
+        if i >= warmups {
+            let dur = end - start;
+            samples.push(size as f64 / dur.as_secs_f64());
+        }
+    }
+    stats::report("randread", size, threads, &samples, output);
+}
+
+fn randwrite_test(
+    size: usize,
+    threads: usize,
+    page_size: usize,
+    repetitions: usize,
+    warmups: usize,
+    output: OutputFormat,
+) {
+    let mut buf = Vec::<u8>::with_capacity(size);
+
+    // it's important to touch all allocated pages, we don't want to count the page faults the
+    // first time they're used
+    buf.resize(size, 0xBE);
+
+    let npages = size.div_ceil(page_size);
+    let order = shuffled_page_order(npages, 0xC0FFEE);
+    let mut samples = Vec::with_capacity(repetitions - warmups);
+
+    if let OutputFormat::Human = output {
+        println!(
+            "randwrite test of {} on {threads} thread(s), {} page(s) of {}",
+            Bytes(size as f64),
+            npages,
+            Bytes(page_size as f64)
+        );
+    }
+    for i in 0..repetitions {
+        let mut start = std::time::Instant::now();
+        let end;
+        let num_threads = threads.max(1);
+
+        let latch = latches::sync::Latch::new(num_threads + 1);
+        let latch = &latch;
+
+        end = std::thread::scope(|s| {
+            let chunk_size = order.len().div_ceil(num_threads);
+            debug_assert!(chunk_size * num_threads >= order.len());
+            let mut handles = Vec::with_capacity(num_threads);
+            for pages in order.chunks(chunk_size) {
+                let buf = buf.as_ptr() as usize;
+                handles.push(s.spawn(move || {
+                    latch.count_down();
+                    latch.wait();
+                    let start = std::time::Instant::now();
+                    // SAFETY: each thread writes a disjoint set of pages, so the concurrent
+                    // mutable access through the shared pointer never aliases.
+                    let buf = unsafe { std::slice::from_raw_parts_mut(buf as *mut u8, size) };
+                    for &page in pages {
+                        let off = page * page_size;
+                        let end = (off + page_size).min(buf.len());
+                        buf[off..end].fill(0xFE);
+                    }
+                    (start, std::time::Instant::now())
+                }));
+            }
+            latch.count_down();
+            latch.wait();
+            start = std::time::Instant::now();
+
+            let mut end = start;
+            for t in handles {
+                let (tstart, tend) = t.join().unwrap();
+                start = start.min(tstart);
+                end = end.max(tend);
+            }
+            end
+        });
+
+        if i >= warmups {
+            let dur = end - start;
+            samples.push(size as f64 / dur.as_secs_f64());
+        }
+    }
+    stats::report("randwrite", size, threads, &samples, output);
 }
 
 fn main() {
     let args = Cli::parse();
 
     match args.command {
-        Cmd::Memcpy { size, threads } => {
-            memcpy_test(size, threads, args.repetitions + args.warmups, args.warmups)
-        }
-        Cmd::Memset { size, threads } => {
-            memset_test(size, threads, args.repetitions + args.warmups, args.warmups)
-        }
+        Cmd::Memcpy {
+            size,
+            threads,
+            alloc,
+            pipeline,
+            ..
+        } if pipeline => pipeline_copy_test(
+            size,
+            threads,
+            alloc,
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+        Cmd::Memcpy {
+            size,
+            threads,
+            alloc,
+            max_throughput,
+            ..
+        } => memcpy_test(
+            size,
+            threads,
+            alloc,
+            max_throughput.map(|b| b.0),
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+        Cmd::Memset {
+            size,
+            threads,
+            alloc,
+            max_throughput,
+        } => memset_test(
+            size,
+            threads,
+            alloc,
+            max_throughput.map(|b| b.0),
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+        Cmd::Memread { size, threads } => memread_test(
+            size,
+            threads,
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+        Cmd::Randread {
+            size,
+            threads,
+            page_size,
+        } => randread_test(
+            size,
+            threads,
+            page_size,
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+        Cmd::Randwrite {
+            size,
+            threads,
+            page_size,
+        } => randwrite_test(
+            size,
+            threads,
+            page_size,
+            args.repetitions + args.warmups,
+            args.warmups,
+            args.output,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffled_page_order_is_a_permutation() {
+        let mut order = shuffled_page_order(100, 0xC0FFEE);
+
+        assert_eq!(order.len(), 100);
+        order.sort();
+        assert_eq!(order, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_shuffled_page_order_is_deterministic_for_a_seed() {
+        let a = shuffled_page_order(50, 42);
+        let b = shuffled_page_order(50, 42);
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_shuffled_page_order_differs_across_seeds() {
+        let a = shuffled_page_order(50, 1);
+        let b = shuffled_page_order(50, 2);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_split_mix64_does_not_repeat_immediately() {
+        let mut rng = SplitMix64(0xC0FFEE);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+
+        assert_ne!(a, b);
     }
 }
@@ -0,0 +1,128 @@
+use clap_derive::ValueEnum;
+
+use crate::fmtbytes::Bytes;
+
+/// how a test's result summary is printed
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// a human-readable summary, using the `Bytes` unit formatting
+    Human,
+    /// a single line of JSON with the raw samples and computed stats, for CI/regression tooling
+    Json,
+}
+
+/// summary statistics (in bytes/sec) over a test's reported repetitions
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn compute(samples: &[f64]) -> Self {
+        assert!(!samples.is_empty(), "can't compute stats of zero samples");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let n = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Self {
+            min: sorted[0],
+            max: sorted[n - 1],
+            mean,
+            median: percentile(&sorted, 0.5),
+            p95: percentile(&sorted, 0.95),
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+/// `sorted` must already be sorted ascending
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let idx = (((sorted.len() - 1) as f64) * p).round() as usize;
+    sorted[idx]
+}
+
+/// prints the samples collected for one test's reported repetitions, either as a human-readable
+/// summary or as a single line of JSON for CI/regression tooling
+pub fn report(test: &str, size: usize, threads: usize, samples: &[f64], format: OutputFormat) {
+    if samples.is_empty() {
+        match format {
+            OutputFormat::Human => println!("{test}: 0 sample(s), nothing to report"),
+            OutputFormat::Json => {
+                println!("{{\"test\":\"{test}\",\"size\":{size},\"threads\":{threads},\"samples\":[],\"stats\":null}}")
+            }
+        }
+        return;
+    }
+
+    let stats = Stats::compute(samples);
+
+    match format {
+        OutputFormat::Human => {
+            println!("{test}: {} sample(s)", samples.len());
+            println!("  min:    {}/s", Bytes(stats.min));
+            println!("  max:    {}/s", Bytes(stats.max));
+            println!("  mean:   {}/s", Bytes(stats.mean));
+            println!("  median: {}/s", Bytes(stats.median));
+            println!("  p95:    {}/s", Bytes(stats.p95));
+            println!("  stddev: {}/s", Bytes(stats.stddev));
+        }
+        OutputFormat::Json => {
+            let samples = samples
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            println!(
+                "{{\"test\":\"{test}\",\"size\":{size},\"threads\":{threads},\"samples\":[{samples}],\"stats\":{{\"min\":{},\"max\":{},\"mean\":{},\"median\":{},\"p95\":{},\"stddev\":{}}}}}",
+                stats.min, stats.max, stats.mean, stats.median, stats.p95, stats.stddev
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_basic_stats() {
+        let stats = Stats::compute(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 5.0);
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.median, 3.0);
+    }
+
+    #[test]
+    fn test_compute_ignores_input_order() {
+        let sorted = Stats::compute(&[1.0, 2.0, 3.0, 4.0]);
+        let shuffled = Stats::compute(&[3.0, 1.0, 4.0, 2.0]);
+
+        assert_eq!(sorted.min, shuffled.min);
+        assert_eq!(sorted.max, shuffled.max);
+        assert_eq!(sorted.mean, shuffled.mean);
+        assert_eq!(sorted.median, shuffled.median);
+    }
+
+    #[test]
+    fn test_percentile_p95_picks_near_top_sample() {
+        let sorted: Vec<f64> = (1..=20).map(|x| x as f64).collect();
+
+        assert_eq!(percentile(&sorted, 0.95), 19.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't compute stats of zero samples")]
+    fn test_compute_panics_on_empty_samples() {
+        Stats::compute(&[]);
+    }
+}
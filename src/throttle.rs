@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+/// chunk size the throttled copy/set loops refill against: small enough to keep the achieved
+/// rate responsive to `--max-throughput`, large enough to not be dominated by lock overhead
+pub const THROTTLE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// token-bucket rate limiter: accumulates tokens at `rate` tokens/sec up to `capacity`, and
+/// blocks the caller in `consume` until enough tokens are available to cover the request
+pub struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// a bucket whose capacity equals one second's worth of `rate` bytes/sec
+    pub fn new(rate: f64) -> Self {
+        Self {
+            capacity: rate,
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// blocks until `n` bytes worth of tokens are available, then withdraws them
+    pub fn consume(&mut self, n: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+
+        if self.tokens < n {
+            let wait = (n - self.tokens) / self.rate;
+            std::thread::sleep(Duration::from_secs_f64(wait));
+            self.tokens = (self.tokens + wait * self.rate).min(self.capacity);
+            self.last_refill = Instant::now();
+        }
+
+        self.tokens -= n;
+    }
+}